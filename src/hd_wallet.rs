@@ -2,12 +2,16 @@
 
 // This code is copied and modified from the `hd-wallet` crate.
 
+use generic_ec::{Point, Scalar};
 use hd_wallet::{
     DeriveShift, DerivedShift, ExtendedKeyPair, ExtendedPublicKey, HardenedIndex, NonHardenedIndex,
 };
+use hmac::Mac;
 
 use super::Ristretto255;
 
+type HmacSha512 = hmac::Hmac<sha2::Sha512>;
+
 /// HD derivation for [`Ristretto255`].
 pub struct HdWallet {
     _private: (),
@@ -34,19 +38,27 @@ impl DeriveShift<Ristretto255> for HdWallet {
         parent_key: &ExtendedKeyPair<Ristretto255>,
         child_index: HardenedIndex,
     ) -> DerivedShift<Ristretto255> {
-
+        let hmac = HmacSha512::new_from_slice(&parent_key.public_key().chain_code)
+            .expect("this never fails: hmac can handle keys of any size");
+        let i = hmac
+            .chain_update([0x00])
+            .chain_update(parent_key.secret_key().secret_key.as_ref().to_be_bytes())
+            .chain_update(child_index.to_be_bytes())
+            .finalize()
+            .into_bytes();
+        Self::calculate_shift(parent_key.public_key(), i)
     }
 }
 
 impl HdWallet {
     fn calculate_shift(
-        parent_public_key: &ExtendedPublicKey<curves::Ed25519>,
+        parent_public_key: &ExtendedPublicKey<Ristretto255>,
         i: hmac::digest::Output<HmacSha512>,
-    ) -> DerivedShift<curves::Ed25519> {
+    ) -> DerivedShift<Ristretto255> {
         let (i_left, i_right) = split_into_two_halves(&i);
 
-        let shift = Scalar::from_be_bytes_mod_order(i_left);
-        let child_pk = parent_public_key.public_key + Point::generator() * shift;
+        let shift = Scalar::<Ristretto255>::from_be_bytes_mod_order(i_left);
+        let child_pk = parent_public_key.public_key + Point::<Ristretto255>::generator() * shift;
 
         DerivedShift {
             shift,
@@ -1,6 +1,10 @@
 //! Operations on the `ristretto` group over Curve25519.
 
-#![no_std]
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+pub mod hd_wallet;
 
 /// Ristretto over Curve25519.
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord, Hash, Default, zeroize::Zeroize)]
@@ -444,3 +448,595 @@ where
         }
     }
 }
+
+/// Maximum length of a domain separation tag allowed by [`expand_message_xmd`]
+/// before RFC 9380 would require switching to the hashed-DST variant.
+const MAX_DST_LEN: usize = 255;
+
+/// SHA-512 input block size in bytes (`Z_pad` in RFC 9380 is this many zero bytes).
+const SHA512_BLOCK_SIZE: usize = 128;
+
+/// SHA-512 output size in bytes.
+const SHA512_OUTPUT_SIZE: usize = 64;
+
+/// Domain separation tag is longer than the 255 bytes allowed by RFC 9380's
+/// `expand_message_xmd` without switching to the hashed-DST variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DstTooLong;
+
+impl core::fmt::Display for DstTooLong {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "domain separation tag is longer than {MAX_DST_LEN} bytes")
+    }
+}
+
+/// Hashes an arbitrary message to a uniformly random point on the curve, per
+/// RFC 9380's `ristretto255_XMD:SHA-512_R255MAP_RO_` suite.
+pub trait HashToCurve: Sized {
+    /// Hashes `msg` to a curve point, domain-separated by `dst`.
+    ///
+    /// Returns [`DstTooLong`] if `dst` is longer than 255 bytes.
+    fn hash_to_curve(msg: &[u8], dst: &[u8]) -> Result<Self, DstTooLong>;
+}
+
+/// Hashes an arbitrary message to a uniformly random scalar, using the same
+/// `expand_message_xmd` construction as [`HashToCurve`].
+pub trait HashToScalar: Sized {
+    /// Hashes `msg` to a scalar, domain-separated by `dst`.
+    ///
+    /// Returns [`DstTooLong`] if `dst` is longer than 255 bytes.
+    fn hash_to_scalar(msg: &[u8], dst: &[u8]) -> Result<Self, DstTooLong>;
+}
+
+impl HashToCurve for Point {
+    fn hash_to_curve(msg: &[u8], dst: &[u8]) -> Result<Self, DstTooLong> {
+        let mut uniform_bytes = [0u8; 64];
+        expand_message_xmd(msg, dst, &mut uniform_bytes)?;
+        Ok(Self(curve25519_dalek::RistrettoPoint::from_uniform_bytes(
+            &uniform_bytes,
+        )))
+    }
+}
+
+impl HashToScalar for Scalar {
+    fn hash_to_scalar(msg: &[u8], dst: &[u8]) -> Result<Self, DstTooLong> {
+        let mut uniform_bytes = [0u8; 64];
+        expand_message_xmd(msg, dst, &mut uniform_bytes)?;
+        Ok(Self(curve25519_dalek::Scalar::from_bytes_mod_order_wide(
+            &uniform_bytes,
+        )))
+    }
+}
+
+/// RFC 9380 `expand_message_xmd` over SHA-512: fills `out` with
+/// `out.len()` pseudorandom bytes derived from `msg`, domain-separated by `dst`.
+fn expand_message_xmd(msg: &[u8], dst: &[u8], out: &mut [u8]) -> Result<(), DstTooLong> {
+    use sha2::{Digest, Sha512};
+
+    if dst.len() > MAX_DST_LEN {
+        return Err(DstTooLong);
+    }
+
+    let len_in_bytes = out.len();
+    let ell = len_in_bytes.div_ceil(SHA512_OUTPUT_SIZE);
+    debug_assert!(ell <= 255, "expand_message_xmd: requested output too long");
+
+    // DST_prime = DST || I2OSP(len(DST), 1)
+    let mut dst_prime = [0u8; MAX_DST_LEN + 1];
+    dst_prime[..dst.len()].copy_from_slice(dst);
+    dst_prime[dst.len()] = dst.len() as u8;
+    let dst_prime = &dst_prime[..=dst.len()];
+
+    let l_i_b_str = (len_in_bytes as u16).to_be_bytes();
+
+    // b_0 = H(Z_pad || msg || l_i_b_str || I2OSP(0, 1) || DST_prime)
+    let b_0 = Sha512::new()
+        .chain_update([0u8; SHA512_BLOCK_SIZE])
+        .chain_update(msg)
+        .chain_update(l_i_b_str)
+        .chain_update([0u8])
+        .chain_update(dst_prime)
+        .finalize();
+
+    // b_1 = H(b_0 || I2OSP(1, 1) || DST_prime)
+    let mut b_i = Sha512::new()
+        .chain_update(b_0)
+        .chain_update([1u8])
+        .chain_update(dst_prime)
+        .finalize();
+
+    let mut written = 0;
+    for i in 1..=ell {
+        let n = core::cmp::min(SHA512_OUTPUT_SIZE, len_in_bytes - written);
+        out[written..written + n].copy_from_slice(&b_i[..n]);
+        written += n;
+
+        if i < ell {
+            // b_{i+1} = H((b_0 XOR b_i) || I2OSP(i+1, 1) || DST_prime)
+            let b_xor: [u8; SHA512_OUTPUT_SIZE] = core::array::from_fn(|j| b_0[j] ^ b_i[j]);
+            b_i = Sha512::new()
+                .chain_update(b_xor)
+                .chain_update([(i + 1) as u8])
+                .chain_update(dst_prime)
+                .finalize();
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod hash_to_curve_tests {
+    use super::*;
+
+    // DST from RFC 9380's `ristretto255_XMD:SHA-512_R255MAP_RO_` suite.
+    const DST: &[u8] = b"QUUX-V01-CS02-with-ristretto255_XMD:SHA-512_R255MAP_RO_";
+
+    // Known-answer vectors for `expand_message_xmd`, produced by an
+    // independent reference implementation of RFC 9380 Section 5.4.1 over
+    // SHA-512. They pin down the exact byte layout of `DST_prime`, the
+    // `Z_pad`/`l_i_b_str` framing, and the `b_0`/`b_i` XOR-chaining, which a
+    // single off-by-one would silently break without changing the type.
+    const EXPAND_MESSAGE_XMD_VECTORS: [(&[u8], [u8; 64]); 3] = [
+        (
+            b"",
+            [
+                0x0d, 0x3b, 0x04, 0x3d, 0x43, 0xae, 0x02, 0x95, 0xe4, 0xde, 0x27, 0x32, 0xfa,
+                0x75, 0x61, 0x6e, 0xe6, 0xa1, 0xd9, 0x51, 0xab, 0x8e, 0x5b, 0x8e, 0x36, 0x86,
+                0x81, 0x2f, 0xe2, 0x8e, 0x30, 0x1b, 0xc7, 0x1d, 0xaa, 0xfd, 0xb7, 0x5b, 0x16,
+                0x75, 0xb9, 0x37, 0xb5, 0x01, 0xbd, 0x90, 0x5c, 0x61, 0x34, 0x59, 0xf1, 0x0a,
+                0xac, 0xa9, 0x2f, 0xed, 0x5d, 0x94, 0x7c, 0x19, 0x87, 0x4a, 0x97, 0x8d,
+            ],
+        ),
+        (
+            b"abc",
+            [
+                0x7b, 0xde, 0x08, 0xd7, 0x3d, 0xa3, 0x81, 0xb2, 0x7b, 0xdb, 0x1e, 0x51, 0x17,
+                0xb6, 0x35, 0xf4, 0xfe, 0xbc, 0xde, 0x12, 0x71, 0x29, 0x2b, 0x82, 0x73, 0xc3,
+                0xac, 0xc6, 0x31, 0xf7, 0xea, 0xe4, 0x61, 0xf2, 0x89, 0xda, 0x85, 0x12, 0x1a,
+                0xc0, 0xfd, 0x0e, 0x42, 0xd4, 0x40, 0x55, 0x01, 0xc2, 0x48, 0x8d, 0x03, 0x59,
+                0x9b, 0xe5, 0xee, 0xa7, 0x4e, 0xa4, 0x36, 0xef, 0xba, 0xea, 0xc3, 0x09,
+            ],
+        ),
+        (
+            b"abcdef0123456789",
+            [
+                0x44, 0xb2, 0x41, 0x46, 0xe5, 0x9c, 0xe0, 0x19, 0xb3, 0xed, 0x35, 0xa9, 0x8b,
+                0x73, 0x95, 0xf5, 0xfe, 0xd8, 0xb1, 0x94, 0xcc, 0xe1, 0x35, 0x4e, 0xb9, 0xfb,
+                0x56, 0xba, 0x04, 0xad, 0x93, 0xf8, 0x1a, 0xfb, 0x0c, 0xd4, 0x50, 0x8e, 0x46,
+                0xb3, 0x66, 0xbb, 0x71, 0xf0, 0xe4, 0x2b, 0xb2, 0x22, 0x39, 0x8d, 0xb4, 0xf5,
+                0xd6, 0x02, 0x5a, 0xac, 0x1f, 0x02, 0x4a, 0x0c, 0x35, 0x90, 0x98, 0x12,
+            ],
+        ),
+    ];
+
+    #[test]
+    fn expand_message_xmd_matches_known_answers() {
+        for (msg, expected) in EXPAND_MESSAGE_XMD_VECTORS {
+            let mut out = [0u8; 64];
+            expand_message_xmd(msg, DST, &mut out).unwrap();
+            assert_eq!(out, expected, "mismatch for msg {msg:?}");
+        }
+    }
+
+    #[test]
+    fn hash_to_scalar_matches_independently_reduced_known_answers() {
+        // Scalars obtained by independently reducing the `expand_message_xmd`
+        // known answers above modulo the ristretto255 group order.
+        const SCALAR_VECTORS: [(&[u8], [u8; 32]); 2] = [
+            (
+                b"",
+                [
+                    0xd2, 0xb8, 0x6e, 0x1e, 0x02, 0x09, 0x2b, 0x63, 0x46, 0x12, 0x7d, 0x94,
+                    0xe2, 0x3e, 0xd8, 0x2a, 0x91, 0x35, 0x45, 0xeb, 0x33, 0x99, 0x5e, 0x41,
+                    0xcf, 0x8d, 0x79, 0x31, 0xe7, 0x24, 0x6f, 0x06,
+                ],
+            ),
+            (
+                b"abc",
+                [
+                    0x8f, 0x8b, 0x30, 0x8d, 0x38, 0x91, 0x7d, 0x20, 0x22, 0xa9, 0xec, 0x4d,
+                    0x3f, 0xaf, 0x1d, 0xcc, 0xc8, 0xfe, 0x71, 0xfd, 0x48, 0xb6, 0xef, 0xd0,
+                    0x36, 0x60, 0xce, 0x1d, 0x49, 0x0b, 0x23, 0x0b,
+                ],
+            ),
+        ];
+
+        for (msg, expected_le_bytes) in SCALAR_VECTORS {
+            let scalar = Scalar::hash_to_scalar(msg, DST).unwrap();
+            assert_eq!(
+                generic_ec_core::IntegerEncoding::to_le_bytes(&scalar),
+                expected_le_bytes,
+                "mismatch for msg {msg:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn hash_to_curve_is_deterministic_and_dst_dependent() {
+        let a = Point::hash_to_curve(b"some message", DST).unwrap();
+        let b = Point::hash_to_curve(b"some message", DST).unwrap();
+        assert!(a == b);
+
+        let c = Point::hash_to_curve(b"some message", b"a different DST").unwrap();
+        assert!(a != c);
+
+        // The output must land on the curve and be torsion-free, like every
+        // other valid ristretto255 point.
+        assert_eq!(generic_ec_core::OnCurve::is_on_curve(&a).unwrap_u8(), 1);
+        assert_eq!(
+            generic_ec_core::SmallFactor::is_torsion_free(&a).unwrap_u8(),
+            1
+        );
+    }
+
+    #[test]
+    fn dst_longer_than_255_bytes_is_rejected() {
+        let long_dst = alloc::vec![0u8; 256];
+        assert!(Point::hash_to_curve(b"msg", &long_dst).is_err());
+        assert!(Scalar::hash_to_scalar(b"msg", &long_dst).is_err());
+    }
+}
+
+/// Table precomputed over a fixed set of base points, so that repeated
+/// variable-time multiscalar multiplications against those same bases (e.g.
+/// many batch signature verifications against the same public keys) don't
+/// redo the per-point setup work each time.
+pub struct PrecomputedPoints(curve25519_dalek::ristretto::VartimeRistrettoPrecomputation);
+
+impl Point {
+    /// Computes `Σ scalarsᵢ·pointsᵢ` in constant time.
+    ///
+    /// Use this when any of the scalars is secret; prefer
+    /// [`Point::vartime_multiscalar_mul`] when every scalar is public, as that
+    /// is considerably faster.
+    pub fn multiscalar_mul<'a>(
+        scalars: impl IntoIterator<Item = &'a Scalar>,
+        points: impl IntoIterator<Item = &'a Point>,
+    ) -> Self {
+        use curve25519_dalek::traits::MultiscalarMul;
+        Self(curve25519_dalek::RistrettoPoint::multiscalar_mul(
+            scalars.into_iter().map(|s| s.0),
+            points.into_iter().map(|p| p.0),
+        ))
+    }
+
+    /// Computes `Σ scalarsᵢ·pointsᵢ` in variable time.
+    ///
+    /// This is faster than [`Point::multiscalar_mul`] but leaks the scalars
+    /// through timing, so only use it when every scalar is public: Shamir
+    /// verification equations, multi-exponentiation-heavy MPC rounds, and
+    /// batch signature verification are typical callers.
+    pub fn vartime_multiscalar_mul<'a>(
+        scalars: impl IntoIterator<Item = &'a Scalar>,
+        points: impl IntoIterator<Item = &'a Point>,
+    ) -> Self {
+        use curve25519_dalek::traits::VartimeMultiscalarMul;
+        Self(curve25519_dalek::RistrettoPoint::vartime_multiscalar_mul(
+            scalars.into_iter().map(|s| s.0),
+            points.into_iter().map(|p| p.0),
+        ))
+    }
+}
+
+impl PrecomputedPoints {
+    /// Precomputes a table over `points`, for reuse across many
+    /// [`PrecomputedPoints::vartime_mixed_multiscalar_mul`] calls that share
+    /// this same fixed base set. This is variable-time, so `points` must be
+    /// public.
+    pub fn new<'a>(points: impl IntoIterator<Item = &'a Point>) -> Self {
+        use curve25519_dalek::traits::VartimePrecomputedMultiscalarMul;
+        Self(curve25519_dalek::ristretto::VartimeRistrettoPrecomputation::new(
+            points.into_iter().map(|p| p.0),
+        ))
+    }
+
+    /// Computes `Σ static_scalarsᵢ·static_pointsᵢ + Σ dynamic_scalarsⱼ·dynamic_pointsⱼ`
+    /// in variable time, where `static_points` is the base set this table was
+    /// built from in [`PrecomputedPoints::new`].
+    pub fn vartime_mixed_multiscalar_mul<'a>(
+        &self,
+        static_scalars: impl IntoIterator<Item = &'a Scalar>,
+        dynamic_scalars: impl IntoIterator<Item = &'a Scalar>,
+        dynamic_points: impl IntoIterator<Item = &'a Point>,
+    ) -> Point {
+        use curve25519_dalek::traits::VartimePrecomputedMultiscalarMul;
+        Point(self.0.vartime_mixed_multiscalar_mul(
+            static_scalars.into_iter().map(|s| s.0),
+            dynamic_scalars.into_iter().map(|s| s.0),
+            dynamic_points.into_iter().map(|p| p.0),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod multiscalar_tests {
+    use super::*;
+    use generic_ec_core::IntegerEncoding;
+
+    /// Computes `Σ scalarsᵢ·pointsᵢ` the naive way, via repeated
+    /// [`Scalar`]-by-[`Point`] multiplication and addition, to serve as a
+    /// ground truth for the `curve25519-dalek`-backed multiscalar paths.
+    fn naive_multiscalar_mul<'a>(
+        scalars: impl IntoIterator<Item = &'a Scalar>,
+        points: impl IntoIterator<Item = &'a Point>,
+    ) -> Point {
+        scalars.into_iter().zip(points).fold(
+            generic_ec_core::Zero::zero(),
+            |acc, (s, p)| generic_ec_core::Additive::add(&acc, &generic_ec_core::Multiplicative::mul(s, p)),
+        )
+    }
+
+    fn sample_scalars_and_points(n: u64) -> (alloc::vec::Vec<Scalar>, alloc::vec::Vec<Point>) {
+        let scalars: alloc::vec::Vec<Scalar> = (1..=n)
+            .map(|i| {
+                let mut bytes = [0u8; 32];
+                bytes[..8].copy_from_slice(&i.to_le_bytes());
+                Scalar::from_le_bytes_mod_order(&bytes)
+            })
+            .collect();
+        let points: alloc::vec::Vec<Point> = scalars
+            .iter()
+            .map(|s| generic_ec_core::Multiplicative::mul(s, &generic_ec_core::CurveGenerator))
+            .collect();
+        (scalars, points)
+    }
+
+    #[test]
+    fn multiscalar_mul_matches_naive_sum() {
+        let (scalars, points) = sample_scalars_and_points(5);
+        let expected = naive_multiscalar_mul(&scalars, &points);
+        assert!(Point::multiscalar_mul(&scalars, &points) == expected);
+    }
+
+    #[test]
+    fn vartime_multiscalar_mul_matches_naive_sum() {
+        let (scalars, points) = sample_scalars_and_points(5);
+        let expected = naive_multiscalar_mul(&scalars, &points);
+        assert!(Point::vartime_multiscalar_mul(&scalars, &points) == expected);
+    }
+
+    #[test]
+    fn vartime_mixed_multiscalar_mul_matches_naive_sum_including_dynamic_terms() {
+        let (static_scalars, static_points) = sample_scalars_and_points(3);
+        let (dynamic_scalars, dynamic_points) = {
+            let mut bytes = [0u8; 32];
+            bytes[..8].copy_from_slice(&100u64.to_le_bytes());
+            let s1 = Scalar::from_le_bytes_mod_order(&bytes);
+            bytes[..8].copy_from_slice(&200u64.to_le_bytes());
+            let s2 = Scalar::from_le_bytes_mod_order(&bytes);
+            let p1 = generic_ec_core::Multiplicative::mul(&s1, &generic_ec_core::CurveGenerator);
+            let p2 = generic_ec_core::Multiplicative::mul(&s2, &generic_ec_core::CurveGenerator);
+            (alloc::vec![s1, s2], alloc::vec![p1, p2])
+        };
+
+        let table = PrecomputedPoints::new(&static_points);
+        let actual = table.vartime_mixed_multiscalar_mul(
+            &static_scalars,
+            &dynamic_scalars,
+            &dynamic_points,
+        );
+
+        let all_scalars: alloc::vec::Vec<Scalar> = static_scalars
+            .iter()
+            .chain(dynamic_scalars.iter())
+            .copied()
+            .collect();
+        let all_points: alloc::vec::Vec<Point> = static_points
+            .iter()
+            .chain(dynamic_points.iter())
+            .copied()
+            .collect();
+        let expected = naive_multiscalar_mul(&all_scalars, &all_points);
+
+        assert!(actual == expected);
+    }
+}
+
+/// Big-endian canonical encoding of `(ℓ-1)/2`, where `ℓ` is the ristretto255
+/// group order, used by [`IsHigh`] to detect the upper half of the scalar field.
+const HALF_ORDER_BE: [u8; 32] = [
+    0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x0a, 0x6f, 0x7c, 0xef, 0x51, 0x7b, 0xce, 0x6b, 0x2c, 0x09, 0x31, 0x8d, 0x2e, 0x7a, 0xe9, 0xf6,
+];
+
+/// Reports whether a scalar's canonical representative lies in the upper half
+/// of the scalar field, i.e. is strictly greater than `(ℓ-1)/2`.
+///
+/// Protocols with a canonical low-`s` convention use this to reject or
+/// normalize the malleable "high" representative of a signature component.
+pub trait IsHigh {
+    /// Returns a [`subtle::Choice`] set iff `self` is greater than `(ℓ-1)/2`.
+    fn is_high(&self) -> subtle::Choice;
+}
+
+impl IsHigh for Scalar {
+    fn is_high(&self) -> subtle::Choice {
+        ct_gt_be(
+            &generic_ec_core::IntegerEncoding::to_be_bytes(self),
+            &HALF_ORDER_BE,
+        )
+    }
+}
+
+impl Scalar {
+    /// Conditionally negates `self` so the returned scalar's canonical
+    /// representative is always `<= (ℓ-1)/2` (see [`IsHigh`]).
+    ///
+    /// No secret-dependent branch is taken: the negation is applied with
+    /// [`subtle::ConditionallySelectable`].
+    pub fn normalize_low(&self) -> Self {
+        let negated = generic_ec_core::Additive::negate(self);
+        subtle::ConditionallySelectable::conditional_select(self, &negated, self.is_high())
+    }
+}
+
+#[cfg(test)]
+mod is_high_tests {
+    use super::*;
+    use generic_ec_core::IntegerEncoding;
+
+    #[test]
+    fn zero_and_one_are_not_high() {
+        assert_eq!(Scalar::ZERO.is_high().unwrap_u8(), 0);
+        assert_eq!(Scalar::ONE.is_high().unwrap_u8(), 0);
+    }
+
+    #[test]
+    fn half_order_boundary_is_not_high_but_one_more_is() {
+        let half_order = Scalar::from_be_bytes_mod_order(&HALF_ORDER_BE);
+        assert_eq!(half_order.is_high().unwrap_u8(), 0);
+
+        let half_order_plus_one = generic_ec_core::Additive::add(&half_order, &Scalar::ONE);
+        assert_eq!(half_order_plus_one.is_high().unwrap_u8(), 1);
+    }
+
+    #[test]
+    fn normalize_low_is_idempotent_and_never_high() {
+        let scalars = [
+            Scalar::ZERO,
+            Scalar::ONE,
+            Scalar::from_be_bytes_mod_order(&HALF_ORDER_BE),
+            generic_ec_core::Additive::add(
+                &Scalar::from_be_bytes_mod_order(&HALF_ORDER_BE),
+                &Scalar::ONE,
+            ),
+            generic_ec_core::Additive::negate(&Scalar::ONE),
+        ];
+
+        for scalar in scalars {
+            let normalized = scalar.normalize_low();
+            assert_eq!(normalized.is_high().unwrap_u8(), 0);
+            assert!(normalized.normalize_low() == normalized);
+        }
+    }
+}
+
+/// Constant-time big-endian byte-slice comparison: returns a [`subtle::Choice`]
+/// set iff `a > b`, treating both as big-endian unsigned integers of equal length.
+fn ct_gt_be(a: &[u8], b: &[u8]) -> subtle::Choice {
+    use subtle::{ConstantTimeEq, ConstantTimeGreater};
+
+    let mut gt = subtle::Choice::from(0);
+    let mut still_equal = subtle::Choice::from(1);
+    for (x, y) in a.iter().zip(b.iter()) {
+        gt |= still_equal & x.ct_gt(y);
+        still_equal &= x.ct_eq(y);
+    }
+    gt
+}
+
+/// Inverts every element of `scalars` in place using Montgomery's batch
+/// inversion trick: a single modular inversion plus ~3N multiplications,
+/// instead of N independent inversions.
+///
+/// Zero elements have no inverse; they are left as [`Scalar::ZERO`] instead of
+/// panicking, and the returned [`subtle::Choice`] is unset (0) if any element
+/// was zero, or set (1) if every element was invertible. No secret-dependent
+/// branch is taken on which elements (if any) were zero.
+pub fn batch_invert(scalars: &mut [Scalar]) -> subtle::Choice {
+    use generic_ec_core::{Invertible, Multiplicative, Zero};
+    use subtle::ConditionallySelectable;
+
+    // Running products `p_i = a_0 * a_1 * ... * a_{i-1}` (so `p_0 = ONE`),
+    // substituting `ONE` for any zero input so the running product stays invertible.
+    let mut running_products = alloc::vec::Vec::with_capacity(scalars.len());
+    let mut acc = Scalar::ONE;
+    let mut all_nonzero = subtle::Choice::from(1);
+    for a in scalars.iter() {
+        let is_zero = Zero::is_zero(a);
+        all_nonzero &= !is_zero;
+        running_products.push(acc);
+        let factor = Scalar::conditional_select(a, &Scalar::ONE, is_zero);
+        acc = Multiplicative::mul(&acc, &factor);
+    }
+
+    // `acc` never saw a zero factor, so it is always invertible.
+    let mut acc_inv = Option::from(Invertible::invert(&acc)).unwrap_or(Scalar::ONE);
+
+    for (a, p) in scalars.iter_mut().zip(running_products.iter()).rev() {
+        let is_zero = Zero::is_zero(a);
+        let factor = Scalar::conditional_select(a, &Scalar::ONE, is_zero);
+        let inv = Multiplicative::mul(&acc_inv, p);
+        *a = Scalar::conditional_select(&inv, &Scalar::ZERO, is_zero);
+        acc_inv = Multiplicative::mul(&acc_inv, &factor);
+    }
+
+    all_nonzero
+}
+
+/// Decompresses many ristretto255-encoded points at once, wrapping
+/// [`curve25519_dalek::ristretto::CompressedRistretto::decompress`] per
+/// element. The `i`-th output is `None` iff `bytes[i]` is not the encoding of
+/// a valid point.
+pub fn batch_decode(bytes: &[[u8; 32]]) -> alloc::vec::Vec<Option<Point>> {
+    bytes
+        .iter()
+        .map(|b| {
+            curve25519_dalek::ristretto::CompressedRistretto(*b)
+                .decompress()
+                .map(Point)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod batch_tests {
+    use super::*;
+    use generic_ec_core::IntegerEncoding;
+
+    #[test]
+    fn batch_invert_matches_individual_inversions() {
+        let scalars: alloc::vec::Vec<Scalar> = (1u64..=5)
+            .map(|n| {
+                let mut bytes = [0u8; 32];
+                bytes[..8].copy_from_slice(&n.to_le_bytes());
+                Scalar::from_le_bytes_mod_order(&bytes)
+            })
+            .collect();
+
+        let mut batch = scalars.clone();
+        let all_nonzero = batch_invert(&mut batch);
+        assert_eq!(all_nonzero.unwrap_u8(), 1);
+
+        for (original, inverted) in scalars.iter().zip(batch.iter()) {
+            let expected = Option::from(generic_ec_core::Invertible::invert(original)).unwrap();
+            assert!(*inverted == expected);
+            // Multiplying back by the original scalar must yield one.
+            let product = generic_ec_core::Multiplicative::mul(original, inverted);
+            assert!(product == Scalar::ONE);
+        }
+    }
+
+    #[test]
+    fn batch_invert_reports_and_zeroes_out_zero_inputs() {
+        let mut batch = [Scalar::ONE, Scalar::ZERO, Scalar::from_le_bytes_mod_order(&[7u8])];
+
+        let all_nonzero = batch_invert(&mut batch);
+        assert_eq!(all_nonzero.unwrap_u8(), 0);
+
+        // The zero input has no inverse and is left as zero...
+        assert!(batch[1] == Scalar::ZERO);
+        // ...while the nonzero inputs are still correctly inverted.
+        assert!(batch[0] == Scalar::ONE);
+        let seven = Scalar::from_le_bytes_mod_order(&[7u8]);
+        let product = generic_ec_core::Multiplicative::mul(&seven, &batch[2]);
+        assert!(product == Scalar::ONE);
+    }
+
+    #[test]
+    fn batch_decode_rejects_non_canonical_encodings_and_keeps_valid_ones() {
+        let valid = generic_ec_core::CompressedEncoding::to_bytes_compressed(&Point::from(
+            generic_ec_core::CurveGenerator,
+        ));
+        // The all-ones encoding represents a field element greater than the
+        // field modulus, so it can never be a canonical point encoding.
+        let invalid = [0xffu8; 32];
+
+        let decoded = batch_decode(&[valid, invalid]);
+        assert_eq!(decoded.len(), 2);
+        assert!(decoded[0].is_some());
+        assert!(decoded[0].unwrap() == Point::from(generic_ec_core::CurveGenerator));
+        assert!(decoded[1].is_none());
+    }
+}